@@ -1,21 +1,18 @@
 use std::cell::{Cell, RefCell};
 use std::fmt;
 
-use std::ops::Range;
-
 use num_bigint::BigInt;
-use num_traits::{One, Signed, ToPrimitive, Zero};
+use num_traits::{One, Signed, ToPrimitive};
 
 use crate::function::{OptionalArg, PyFuncArgs};
 use crate::pyobject::{IdProtocol, PyContext, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol};
 use crate::vm::{ReprGuard, VirtualMachine};
 
 use super::objbool;
-use super::objint;
 use super::objiter;
 use super::objsequence::{
     get_elements, get_elements_cell, get_item, seq_equal, seq_ge, seq_gt, seq_le, seq_lt, seq_mul,
-    PySliceableSequence, SequenceIndex,
+    PySliceableSequence, SequenceIndex, SequenceIndexIter,
 };
 use super::objslice::PySliceRef;
 use super::objtype;
@@ -52,48 +49,6 @@ impl PyList {
     pub fn get_len(&self) -> usize {
         self.elements.borrow().len()
     }
-
-    pub fn get_pos(&self, p: i32) -> Option<usize> {
-        // convert a (potentially negative) positon into a real index
-        if p < 0 {
-            if -p as usize > self.get_len() {
-                None
-            } else {
-                Some(self.get_len() - ((-p) as usize))
-            }
-        } else if p as usize >= self.get_len() {
-            None
-        } else {
-            Some(p as usize)
-        }
-    }
-
-    pub fn get_slice_pos(&self, slice_pos: &BigInt) -> usize {
-        if let Some(pos) = slice_pos.to_i32() {
-            if let Some(index) = self.get_pos(pos) {
-                // within bounds
-                return index;
-            }
-        }
-
-        if slice_pos.is_negative() {
-            // slice past start bound, round to start
-            0
-        } else {
-            // slice past end bound, round to end
-            self.get_len()
-        }
-    }
-
-    pub fn get_slice_range(&self, start: &Option<BigInt>, stop: &Option<BigInt>) -> Range<usize> {
-        let start = start.as_ref().map(|x| self.get_slice_pos(x)).unwrap_or(0);
-        let stop = stop
-            .as_ref()
-            .map(|x| self.get_slice_pos(x))
-            .unwrap_or_else(|| self.get_len());
-
-        start..stop
-    }
 }
 
 pub type PyListRef = PyRef<PyList>;
@@ -181,23 +136,63 @@ impl PyListRef {
         }
     }
 
-    fn setitem(self, key: PyObjectRef, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    fn reversed(self, _vm: &VirtualMachine) -> PyListReverseIterator {
+        let position = self.elements.borrow().len() as isize - 1;
+        PyListReverseIterator {
+            position: Cell::new(position),
+            list: self,
+        }
+    }
+
+    fn setitem(self, key: SequenceIndex, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        match key {
+            SequenceIndex::Int(index) => self.setindex(index, value, vm),
+            SequenceIndex::Slice(slice) => self.setslice(slice, value, vm),
+        }
+    }
+
+    fn setindex(self, index: i32, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         let mut elements = self.elements.borrow_mut();
+        if let Some(pos_index) = elements.get_pos(index) {
+            elements[pos_index] = value;
+            Ok(vm.get_none())
+        } else {
+            Err(vm.new_index_error("list index out of range".to_string()))
+        }
+    }
 
-        if objtype::isinstance(&key, &vm.ctx.int_type()) {
-            let idx = objint::get_value(&key).to_i32().unwrap();
-            if let Some(pos_index) = elements.get_pos(idx) {
-                elements[pos_index] = value;
-                Ok(vm.get_none())
+    fn setslice(self, slice: PySliceRef, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let items = vm.extract_elements(&value)?;
+        let len = self.elements.borrow().len();
+
+        if slice.step.as_ref().map_or(true, |s| s.is_one()) {
+            let range = len.get_slice_range(&slice.start, &slice.stop);
+            let range = if range.start <= range.end {
+                range
             } else {
-                Err(vm.new_index_error("list index out of range".to_string()))
-            }
-        } else {
-            panic!(
-                "TypeError: indexing type {:?} with index {:?} is not supported (yet?)",
-                elements, key
-            )
+                range.start..range.start
+            };
+            self.elements.borrow_mut().splice(range, items);
+            return Ok(vm.get_none());
         }
+
+        // extended slice: the number of target positions must match the
+        // number of replacement items exactly, element-by-element.
+        let subscript = SequenceIndex::Slice(slice);
+        let positions = SequenceIndexIter::new(vm, len, &subscript)?;
+        if positions.len() != items.len() {
+            return Err(vm.new_value_error(format!(
+                "attempt to assign sequence of size {} to extended slice of size {}",
+                items.len(),
+                positions.len()
+            )));
+        }
+
+        let mut elements = self.elements.borrow_mut();
+        for (index, item) in positions.zip(items.into_iter()) {
+            elements[index] = item;
+        }
+        Ok(vm.get_none())
     }
 
     fn repr(self, vm: &VirtualMachine) -> PyResult<String> {
@@ -252,8 +247,24 @@ impl PyListRef {
         Ok(false)
     }
 
-    fn index(self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult<usize> {
-        for (index, element) in self.elements.borrow().iter().enumerate() {
+    fn index(
+        self,
+        needle: PyObjectRef,
+        start: OptionalArg<isize>,
+        stop: OptionalArg<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult<usize> {
+        let elements = self.elements.borrow();
+        let start = start.into_option().map(BigInt::from);
+        let stop = stop.into_option().map(BigInt::from);
+        let range = elements.len().get_slice_range(&start, &stop);
+
+        for (index, element) in elements
+            .iter()
+            .enumerate()
+            .take(range.end)
+            .skip(range.start)
+        {
             if needle.is(element) {
                 return Ok(index);
             }
@@ -371,7 +382,9 @@ impl PyListRef {
     }
 
     fn delindex(self, index: i32, vm: &VirtualMachine) -> PyResult {
-        if let Some(pos_index) = self.get_pos(index) {
+        let len = self.elements.borrow().len();
+        let mut positions = SequenceIndexIter::new(vm, len, &SequenceIndex::Int(index))?;
+        if let Some(pos_index) = positions.next() {
             self.elements.borrow_mut().remove(pos_index);
             Ok(vm.get_none())
         } else {
@@ -380,106 +393,42 @@ impl PyListRef {
     }
 
     fn delslice(self, slice: PySliceRef, vm: &VirtualMachine) -> PyResult {
-        let start = &slice.start;
-        let stop = &slice.stop;
-        let step = slice.step.clone().unwrap_or_else(BigInt::one);
-
-        if step.is_zero() {
-            Err(vm.new_value_error("slice step cannot be zero".to_string()))
-        } else if step.is_positive() {
-            let range = self.get_slice_range(&start, &stop);
-            if range.start < range.end {
-                #[allow(clippy::range_plus_one)]
-                match step.to_i32() {
-                    Some(1) => {
-                        self._del_slice(range);
-                        Ok(vm.get_none())
-                    }
-                    Some(num) => {
-                        self._del_stepped_slice(range, num as usize);
-                        Ok(vm.get_none())
-                    }
-                    None => {
-                        self._del_slice(range.start..range.start + 1);
-                        Ok(vm.get_none())
-                    }
-                }
-            } else {
-                // no del to do
-                Ok(vm.get_none())
-            }
+        let len = self.elements.borrow().len();
+        // a negative step walks the touched positions high-to-low; `.rev()`
+        // (available since the iterator is double-ended) recovers ascending
+        // order, which is all `_del_positions` needs to know about.
+        let descending = slice.step.as_ref().map_or(false, |s| s.is_negative());
+        let positions = SequenceIndexIter::new(vm, len, &SequenceIndex::Slice(slice))?;
+        if descending {
+            self._del_positions(positions.rev());
         } else {
-            // calculate the range for the reverse slice, first the bounds needs to be made
-            // exclusive around stop, the lower number
-            let start = start.as_ref().map(|x| x + 1);
-            let stop = stop.as_ref().map(|x| x + 1);
-            let range = self.get_slice_range(&stop, &start);
-            if range.start < range.end {
-                match (-step).to_i32() {
-                    Some(1) => {
-                        self._del_slice(range);
-                        Ok(vm.get_none())
-                    }
-                    Some(num) => {
-                        self._del_stepped_slice_reverse(range, num as usize);
-                        Ok(vm.get_none())
-                    }
-                    None => {
-                        self._del_slice(range.end - 1..range.end);
-                        Ok(vm.get_none())
-                    }
-                }
-            } else {
-                // no del to do
-                Ok(vm.get_none())
-            }
+            self._del_positions(positions);
         }
+        Ok(vm.get_none())
     }
 
-    fn _del_slice(self, range: Range<usize>) {
-        self.elements.borrow_mut().drain(range);
-    }
-
-    fn _del_stepped_slice(self, range: Range<usize>, step: usize) {
-        // no easy way to delete stepped indexes so here is what we'll do
-        let mut deleted = 0;
-        let mut elements = self.elements.borrow_mut();
-        let mut indexes = range.clone().step_by(step).peekable();
-
-        for i in range.clone() {
-            // is this an index to delete?
-            if indexes.peek() == Some(&i) {
-                // record and move on
-                indexes.next();
-                deleted += 1;
-            } else {
-                // swap towards front
-                elements.swap(i - deleted, i);
-            }
+    // Deletes every position `positions` yields (ascending, no duplicates) in
+    // a single left-to-right compaction pass, then drains the now-contiguous
+    // tail. Works unchanged whether the positions came from a contiguous
+    // slice, a stepped slice, or a stepped-and-reversed slice.
+    fn _del_positions<I: ExactSizeIterator<Item = usize>>(self, positions: I) {
+        if positions.len() == 0 {
+            return;
         }
-        // then drain (the values to delete should now be contiguous at the end of the range)
-        elements.drain((range.end - deleted)..range.end);
-    }
-
-    fn _del_stepped_slice_reverse(self, range: Range<usize>, step: usize) {
-        // no easy way to delete stepped indexes so here is what we'll do
-        let mut deleted = 0;
+        let mut positions = positions.peekable();
+        let start = *positions.peek().unwrap();
         let mut elements = self.elements.borrow_mut();
-        let mut indexes = range.clone().rev().step_by(step).peekable();
-
-        for i in range.clone().rev() {
-            // is this an index to delete?
-            if indexes.peek() == Some(&i) {
-                // record and move on
-                indexes.next();
+        let mut deleted = 0;
+        for i in start..elements.len() {
+            if positions.peek() == Some(&i) {
+                positions.next();
                 deleted += 1;
-            } else {
-                // swap towards back
-                elements.swap(i + deleted, i);
+            } else if deleted > 0 {
+                elements.swap(i - deleted, i);
             }
         }
-        // then drain (the values to delete should now be contiguous at teh start of the range)
-        elements.drain(range.start..(range.start + deleted));
+        let end = elements.len();
+        elements.drain((end - deleted)..end);
     }
 }
 
@@ -497,45 +446,69 @@ fn list_new(
     PyList::from(elements).into_ref_with_type(vm, cls)
 }
 
-fn quicksort(
+// Stable merge sort over the parallel keys/values slices. list.sort() must be
+// stable, which a quicksort-style partition cannot guarantee, so we split,
+// recursively sort each half, then merge, always taking the left element on
+// ties so that equal keys keep their original relative order.
+fn merge_sort(
     vm: &VirtualMachine,
     keys: &mut [PyObjectRef],
     values: &mut [PyObjectRef],
 ) -> PyResult<()> {
     let len = values.len();
-    if len >= 2 {
-        let pivot = partition(vm, keys, values)?;
-        quicksort(vm, &mut keys[0..pivot], &mut values[0..pivot])?;
-        quicksort(vm, &mut keys[pivot + 1..len], &mut values[pivot + 1..len])?;
+    if len < 2 {
+        return Ok(());
     }
-    Ok(())
+
+    let mid = len / 2;
+    merge_sort(vm, &mut keys[0..mid], &mut values[0..mid])?;
+    merge_sort(vm, &mut keys[mid..len], &mut values[mid..len])?;
+    merge(vm, keys, values, mid)
 }
 
-fn partition(
+fn merge(
     vm: &VirtualMachine,
     keys: &mut [PyObjectRef],
     values: &mut [PyObjectRef],
-) -> PyResult<usize> {
-    let len = values.len();
-    let pivot = len / 2;
-
-    values.swap(pivot, len - 1);
-    keys.swap(pivot, len - 1);
-
-    let mut store_idx = 0;
-    for i in 0..len - 1 {
-        let result = vm._lt(keys[i].clone(), keys[len - 1].clone())?;
-        let boolval = objbool::boolval(vm, result)?;
-        if boolval {
-            values.swap(i, store_idx);
-            keys.swap(i, store_idx);
-            store_idx += 1;
+    mid: usize,
+) -> PyResult<()> {
+    let merged_keys: Vec<PyObjectRef> = keys.to_vec();
+    let merged_values: Vec<PyObjectRef> = values.to_vec();
+    let (left_keys, right_keys) = merged_keys.split_at(mid);
+    let (left_values, right_values) = merged_values.split_at(mid);
+
+    let mut i = 0;
+    let mut j = 0;
+    let mut k = 0;
+    while i < left_keys.len() && j < right_keys.len() {
+        // only take from the right if it's strictly less than the left, so
+        // that equal keys keep the left (original) element first.
+        let take_right = objbool::boolval(vm, vm._lt(right_keys[j].clone(), left_keys[i].clone())?)?;
+        if take_right {
+            keys[k] = right_keys[j].clone();
+            values[k] = right_values[j].clone();
+            j += 1;
+        } else {
+            keys[k] = left_keys[i].clone();
+            values[k] = left_values[i].clone();
+            i += 1;
         }
+        k += 1;
+    }
+    while i < left_keys.len() {
+        keys[k] = left_keys[i].clone();
+        values[k] = left_values[i].clone();
+        i += 1;
+        k += 1;
+    }
+    while j < right_keys.len() {
+        keys[k] = right_keys[j].clone();
+        values[k] = right_values[j].clone();
+        j += 1;
+        k += 1;
     }
 
-    values.swap(store_idx, len - 1);
-    keys.swap(store_idx, len - 1);
-    Ok(store_idx)
+    Ok(())
 }
 
 fn do_sort(
@@ -553,7 +526,16 @@ fn do_sort(
         });
     }
 
-    quicksort(vm, &mut keys, values)?;
+    // To get the CPython-compatible reverse behaviour (equal elements keep
+    // their original relative order even when reverse=True) without losing
+    // stability, reverse the inputs, stable-sort, then reverse the outputs,
+    // rather than reversing the sorted result directly.
+    if reverse {
+        values.reverse();
+        keys.reverse();
+    }
+
+    merge_sort(vm, &mut keys, values)?;
 
     if reverse {
         values.reverse();
@@ -614,6 +596,49 @@ impl PyListIteratorRef {
     fn iter(self, _vm: &VirtualMachine) -> Self {
         self
     }
+
+    fn length_hint(self, _vm: &VirtualMachine) -> usize {
+        self.list
+            .elements
+            .borrow()
+            .len()
+            .saturating_sub(self.position.get())
+    }
+}
+
+#[derive(Debug)]
+pub struct PyListReverseIterator {
+    pub position: Cell<isize>,
+    pub list: PyListRef,
+}
+
+impl PyValue for PyListReverseIterator {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.ctx.listreverseiterator_type()
+    }
+}
+
+type PyListReverseIteratorRef = PyRef<PyListReverseIterator>;
+
+impl PyListReverseIteratorRef {
+    fn next(self, vm: &VirtualMachine) -> PyResult {
+        let position = self.position.get();
+        if position >= 0 && (position as usize) < self.list.elements.borrow().len() {
+            let ret = self.list.elements.borrow()[position as usize].clone();
+            self.position.set(position - 1);
+            Ok(ret)
+        } else {
+            Err(objiter::new_stop_iteration(vm))
+        }
+    }
+
+    fn iter(self, _vm: &VirtualMachine) -> Self {
+        self
+    }
+
+    fn length_hint(self, _vm: &VirtualMachine) -> usize {
+        (self.position.get() + 1).max(0) as usize
+    }
 }
 
 #[rustfmt::skip] // to avoid line splitting
@@ -637,6 +662,7 @@ pub fn init(context: &PyContext) {
         "__ge__" => context.new_rustfunc(PyListRef::ge),
         "__getitem__" => context.new_rustfunc(PyListRef::getitem),
         "__iter__" => context.new_rustfunc(PyListRef::iter),
+        "__reversed__" => context.new_rustfunc(PyListRef::reversed),
         "__setitem__" => context.new_rustfunc(PyListRef::setitem),
         "__mul__" => context.new_rustfunc(PyListRef::mul),
         "__len__" => context.new_rustfunc(PyListRef::len),
@@ -661,5 +687,13 @@ pub fn init(context: &PyContext) {
     extend_class!(context, listiterator_type, {
         "__next__" => context.new_rustfunc(PyListIteratorRef::next),
         "__iter__" => context.new_rustfunc(PyListIteratorRef::iter),
+        "__length_hint__" => context.new_rustfunc(PyListIteratorRef::length_hint),
+    });
+
+    let listreverseiterator_type = &context.listreverseiterator_type;
+    extend_class!(context, listreverseiterator_type, {
+        "__next__" => context.new_rustfunc(PyListReverseIteratorRef::next),
+        "__iter__" => context.new_rustfunc(PyListReverseIteratorRef::iter),
+        "__length_hint__" => context.new_rustfunc(PyListReverseIteratorRef::length_hint),
     });
 }
@@ -0,0 +1,341 @@
+use std::cell::{Ref, RefCell};
+use std::ops::Range;
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::pyobject::{PyObjectRef, PyResult, TryFromObject, TypeProtocol};
+use crate::vm::VirtualMachine;
+
+use super::objbool;
+use super::objint;
+use super::objlist::PyList;
+use super::objslice::PySliceRef;
+use super::objtype;
+
+/// The parsed form of whatever was passed as the subscript of `obj[key]`:
+/// either a plain integer index, or a slice.
+#[derive(Debug)]
+pub enum SequenceIndex {
+    Int(i32),
+    Slice(PySliceRef),
+}
+
+impl TryFromObject for SequenceIndex {
+    fn try_from_object(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<Self> {
+        if objtype::isinstance(&obj, &vm.ctx.int_type()) {
+            Ok(SequenceIndex::Int(objint::get_value(&obj).to_i32().unwrap()))
+        } else if objtype::isinstance(&obj, &vm.ctx.slice_type()) {
+            Ok(SequenceIndex::Slice(PySliceRef::try_from_object(vm, obj)?))
+        } else {
+            Err(vm.new_type_error(format!(
+                "indices must be integers or slices, not {}",
+                obj.class().name
+            )))
+        }
+    }
+}
+
+/// Negative-index normalization and slice-bounds resolution shared by every
+/// `list`-like sequence (currently just `list`, but written so `tuple` and
+/// `bytearray` can adopt it without duplicating the math).
+pub trait PySliceableSequence {
+    fn len(&self) -> usize;
+
+    fn get_pos(&self, p: i32) -> Option<usize> {
+        if p < 0 {
+            if -p as usize > self.len() {
+                None
+            } else {
+                Some(self.len() - ((-p) as usize))
+            }
+        } else if p as usize >= self.len() {
+            None
+        } else {
+            Some(p as usize)
+        }
+    }
+
+    fn get_slice_pos(&self, slice_pos: &BigInt) -> usize {
+        if let Some(pos) = slice_pos.to_i32() {
+            if let Some(index) = self.get_pos(pos) {
+                // within bounds
+                return index;
+            }
+        }
+
+        if slice_pos.is_negative() {
+            // slice past start bound, round to start
+            0
+        } else {
+            // slice past end bound, round to end
+            self.len()
+        }
+    }
+
+    fn get_slice_range(&self, start: &Option<BigInt>, stop: &Option<BigInt>) -> Range<usize> {
+        let start = start.as_ref().map(|x| self.get_slice_pos(x)).unwrap_or(0);
+        let stop = stop
+            .as_ref()
+            .map(|x| self.get_slice_pos(x))
+            .unwrap_or_else(|| self.len());
+
+        start..stop
+    }
+}
+
+impl PySliceableSequence for [PyObjectRef] {
+    fn len(&self) -> usize {
+        <[PyObjectRef]>::len(self)
+    }
+}
+
+// Letting the resolver work off a bare length (rather than requiring an
+// actual slice of elements) is what lets tuple/bytearray reuse it too.
+impl PySliceableSequence for usize {
+    fn len(&self) -> usize {
+        *self
+    }
+}
+
+/// Resolves one endpoint of a negative-step slice the way CPython's
+/// `slice.indices` does: wrap a negative value by `len`, then clamp into
+/// `-1..=len-1`, where `-1` stands for "below the first element" (no lower
+/// bound). `none_default` is used verbatim when the endpoint was omitted.
+fn resolve_reverse_bound(bound: Option<&BigInt>, len: usize, none_default: isize) -> isize {
+    let bound = match bound {
+        Some(bound) => bound,
+        None => return none_default,
+    };
+    let len = len as i64;
+    let value = bound.to_i64().unwrap_or_else(|| {
+        if bound.is_negative() {
+            i64::min_value()
+        } else {
+            i64::max_value()
+        }
+    });
+    let value = if value < 0 { value + len } else { value };
+    if value < 0 {
+        -1
+    } else if value >= len {
+        (len - 1) as isize
+    } else {
+        value as isize
+    }
+}
+
+/// Iterates the concrete, in-bounds `usize` positions addressed by a
+/// resolved `SequenceIndex` against a container of a given length: a single
+/// position for `Int`, or every position a `Slice` touches (honoring its
+/// step, including negative step) for `Slice`. Exposed as a proper
+/// `ExactSizeIterator` + `DoubleEndedIterator` so callers know the touched
+/// count up front and can walk it from either end (e.g. `.rev()` to recover
+/// ascending order out of a negative-step slice).
+pub struct SequenceIndexIter {
+    next: isize,
+    step: isize,
+    count: usize,
+}
+
+impl SequenceIndexIter {
+    pub fn new(vm: &VirtualMachine, len: usize, subscript: &SequenceIndex) -> PyResult<Self> {
+        match subscript {
+            SequenceIndex::Int(index) => Ok(match len.get_pos(*index) {
+                Some(pos) => SequenceIndexIter {
+                    next: pos as isize,
+                    step: 1,
+                    count: 1,
+                },
+                None => SequenceIndexIter {
+                    next: 0,
+                    step: 1,
+                    count: 0,
+                },
+            }),
+            SequenceIndex::Slice(slice) => {
+                let step = slice.step.clone().unwrap_or_else(BigInt::one);
+                if step.is_zero() {
+                    return Err(vm.new_value_error("slice step cannot be zero".to_string()));
+                }
+
+                if step.is_positive() {
+                    let range = len.get_slice_range(&slice.start, &slice.stop);
+                    let step = step.to_i32().unwrap_or_else(i32::max_value) as isize;
+                    Ok(if range.start < range.end {
+                        let count = (range.end - range.start - 1) / (step as usize) + 1;
+                        SequenceIndexIter {
+                            next: range.start as isize,
+                            step,
+                            count,
+                        }
+                    } else {
+                        SequenceIndexIter {
+                            next: 0,
+                            step,
+                            count: 0,
+                        }
+                    })
+                } else {
+                    // Resolve each bound the way CPython's slice.indices does for a
+                    // negative step: a negative endpoint is first wrapped by `len`,
+                    // then clamped into `-1..=len-1` (the `-1` sentinel meaning
+                    // "before the first element", i.e. no lower bound). Clamping
+                    // *after* wrapping, rather than shifting the raw endpoint by one
+                    // and handing it to the forward-slice clamp (`get_slice_pos`),
+                    // is what `x[-1::-1]` needs: a raw `-1` must resolve to `len-1`,
+                    // not collide with an unrelated in-bounds `0`.
+                    let top = resolve_reverse_bound(slice.start.as_ref(), len, len as isize - 1);
+                    let bottom = resolve_reverse_bound(slice.stop.as_ref(), len, -1);
+                    let step = (-step).to_i32().unwrap_or_else(i32::max_value) as isize;
+                    Ok(if top > bottom {
+                        let count = (top - bottom - 1) as usize / (step as usize) + 1;
+                        SequenceIndexIter {
+                            next: top,
+                            step: -step,
+                            count,
+                        }
+                    } else {
+                        SequenceIndexIter {
+                            next: 0,
+                            step: -step,
+                            count: 0,
+                        }
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for SequenceIndexIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.count == 0 {
+            return None;
+        }
+        let result = self.next;
+        self.next += self.step;
+        self.count -= 1;
+        Some(result as usize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count, Some(self.count))
+    }
+}
+
+impl DoubleEndedIterator for SequenceIndexIter {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        Some((self.next + self.step * self.count as isize) as usize)
+    }
+}
+
+impl ExactSizeIterator for SequenceIndexIter {}
+
+pub fn get_elements(obj: &PyObjectRef) -> Ref<Vec<PyObjectRef>> {
+    obj.payload::<PyList>()
+        .expect("non-list object passed to get_elements")
+        .elements
+        .borrow()
+}
+
+pub fn get_elements_cell(obj: &PyObjectRef) -> &RefCell<Vec<PyObjectRef>> {
+    &obj.payload::<PyList>()
+        .expect("non-list object passed to get_elements_cell")
+        .elements
+}
+
+pub fn get_item(
+    vm: &VirtualMachine,
+    sequence: &PyObjectRef,
+    elements: &[PyObjectRef],
+    subscript: PyObjectRef,
+) -> PyResult {
+    let needle = SequenceIndex::try_from_object(vm, subscript)?;
+    match needle {
+        SequenceIndex::Int(_) => {
+            let mut positions = SequenceIndexIter::new(vm, elements.len(), &needle)?;
+            positions.next().map(|pos| elements[pos].clone()).ok_or_else(|| {
+                vm.new_index_error(format!("{} index out of range", sequence.class().name))
+            })
+        }
+        SequenceIndex::Slice(_) => {
+            let positions = SequenceIndexIter::new(vm, elements.len(), &needle)?;
+            let sliced: Vec<PyObjectRef> = positions.map(|pos| elements[pos].clone()).collect();
+            Ok(vm.ctx.new_list(sliced))
+        }
+    }
+}
+
+pub fn seq_equal(vm: &VirtualMachine, zelf: &[PyObjectRef], other: &[PyObjectRef]) -> PyResult<bool> {
+    if zelf.len() == other.len() {
+        for (a, b) in zelf.iter().zip(other.iter()) {
+            if !a.is(b) {
+                let eq = vm._eq(a.clone(), b.clone())?;
+                if !objbool::boolval(vm, eq)? {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+fn seq_cmp(
+    vm: &VirtualMachine,
+    zelf: &[PyObjectRef],
+    other: &[PyObjectRef],
+    lt: bool,
+    or_equal: bool,
+) -> PyResult<bool> {
+    for (a, b) in zelf.iter().zip(other.iter()) {
+        if !a.is(b) {
+            let eq = vm._eq(a.clone(), b.clone())?;
+            if !objbool::boolval(vm, eq)? {
+                // `a > b` is just `b < a`, so everything routes through `_lt`.
+                return if lt {
+                    objbool::boolval(vm, vm._lt(a.clone(), b.clone())?)
+                } else {
+                    objbool::boolval(vm, vm._lt(b.clone(), a.clone())?)
+                };
+            }
+        }
+    }
+    if zelf.len() == other.len() {
+        Ok(or_equal)
+    } else {
+        Ok((zelf.len() < other.len()) == lt)
+    }
+}
+
+pub fn seq_lt(vm: &VirtualMachine, zelf: &[PyObjectRef], other: &[PyObjectRef]) -> PyResult<bool> {
+    seq_cmp(vm, zelf, other, true, false)
+}
+
+pub fn seq_gt(vm: &VirtualMachine, zelf: &[PyObjectRef], other: &[PyObjectRef]) -> PyResult<bool> {
+    seq_cmp(vm, zelf, other, false, false)
+}
+
+pub fn seq_le(vm: &VirtualMachine, zelf: &[PyObjectRef], other: &[PyObjectRef]) -> PyResult<bool> {
+    seq_cmp(vm, zelf, other, true, true)
+}
+
+pub fn seq_ge(vm: &VirtualMachine, zelf: &[PyObjectRef], other: &[PyObjectRef]) -> PyResult<bool> {
+    seq_cmp(vm, zelf, other, false, true)
+}
+
+pub fn seq_mul(elements: &[PyObjectRef], counter: isize) -> Vec<PyObjectRef> {
+    if counter <= 0 {
+        vec![]
+    } else {
+        elements.iter().cloned().cycle().take(elements.len() * counter as usize).collect()
+    }
+}
@@ -0,0 +1,174 @@
+//! Implementation of the Python `heapq` module: a binary min-heap that lives
+//! directly in an existing `list`'s backing storage, exactly as CPython's C
+//! extension does.
+
+use crate::function::OptionalArg;
+use crate::obj::objbool;
+use crate::obj::objlist::PyListRef;
+use crate::pyobject::{PyObjectRef, PyResult};
+use crate::vm::VirtualMachine;
+
+// `vm._lt` can re-enter Python (a custom `__lt__`), so every comparison is
+// made on cloned handles with no `RefCell` borrow of `list.elements` held
+// across the call; `get`/`swap` below take and drop their borrow immediately.
+fn heap_lt(vm: &VirtualMachine, a: &PyObjectRef, b: &PyObjectRef) -> PyResult<bool> {
+    let lt = vm._lt(a.clone(), b.clone())?;
+    objbool::boolval(vm, lt)
+}
+
+fn heap_len(list: &PyListRef) -> usize {
+    list.elements.borrow().len()
+}
+
+fn heap_get(list: &PyListRef, index: usize) -> PyObjectRef {
+    list.elements.borrow()[index].clone()
+}
+
+fn heap_swap(list: &PyListRef, i: usize, j: usize) {
+    list.elements.borrow_mut().swap(i, j);
+}
+
+fn sift_up(vm: &VirtualMachine, list: &PyListRef, mut pos: usize) -> PyResult<()> {
+    while pos > 0 {
+        let parent_pos = (pos - 1) / 2;
+        if heap_lt(vm, &heap_get(list, pos), &heap_get(list, parent_pos))? {
+            heap_swap(list, pos, parent_pos);
+            pos = parent_pos;
+        } else {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn sift_down(vm: &VirtualMachine, list: &PyListRef, mut pos: usize) -> PyResult<()> {
+    let len = heap_len(list);
+    loop {
+        let left = 2 * pos + 1;
+        let right = 2 * pos + 2;
+        let mut smallest = pos;
+        if left < len && heap_lt(vm, &heap_get(list, left), &heap_get(list, smallest))? {
+            smallest = left;
+        }
+        if right < len && heap_lt(vm, &heap_get(list, right), &heap_get(list, smallest))? {
+            smallest = right;
+        }
+        if smallest == pos {
+            return Ok(());
+        }
+        heap_swap(list, pos, smallest);
+        pos = smallest;
+    }
+}
+
+fn heapq_heapify(list: PyListRef, vm: &VirtualMachine) -> PyResult<()> {
+    let len = heap_len(&list);
+    for pos in (0..len / 2).rev() {
+        sift_down(vm, &list, pos)?;
+    }
+    Ok(())
+}
+
+fn heapq_heappush(list: PyListRef, item: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+    list.elements.borrow_mut().push(item);
+    sift_up(vm, &list, heap_len(&list) - 1)
+}
+
+fn heapq_heappop(list: PyListRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+    let last = list
+        .elements
+        .borrow_mut()
+        .pop()
+        .ok_or_else(|| vm.new_index_error("index out of range".to_string()))?;
+    if heap_len(&list) == 0 {
+        return Ok(last);
+    }
+    let root = std::mem::replace(&mut list.elements.borrow_mut()[0], last);
+    sift_down(vm, &list, 0)?;
+    Ok(root)
+}
+
+fn heapq_heapreplace(list: PyListRef, item: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+    if heap_len(&list) == 0 {
+        return Err(vm.new_index_error("index out of range".to_string()));
+    }
+    let root = std::mem::replace(&mut list.elements.borrow_mut()[0], item);
+    sift_down(vm, &list, 0)?;
+    Ok(root)
+}
+
+fn heapq_heappushpop(list: PyListRef, item: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+    if heap_len(&list) > 0 && heap_lt(vm, &heap_get(&list, 0), &item)? {
+        let root = std::mem::replace(&mut list.elements.borrow_mut()[0], item);
+        sift_down(vm, &list, 0)?;
+        Ok(root)
+    } else {
+        Ok(item)
+    }
+}
+
+// `nlargest`/`nsmallest` don't need to touch an existing heap at all: collect
+// the iterable, sort it with the same `vm._lt` comparator (applied to the
+// `key(item)` projection when one is given, exactly like `list.sort`'s
+// `key_func`), then slice.
+fn full_sort(
+    vm: &VirtualMachine,
+    items: &mut Vec<PyObjectRef>,
+    key_func: Option<PyObjectRef>,
+) -> PyResult<()> {
+    let mut keys = Vec::with_capacity(items.len());
+    for x in items.iter() {
+        keys.push(match &key_func {
+            None => x.clone(),
+            Some(func) => vm.invoke(func.clone(), vec![x.clone()])?,
+        });
+    }
+
+    for i in 1..items.len() {
+        let mut j = i;
+        while j > 0 && heap_lt(vm, &keys[j], &keys[j - 1])? {
+            keys.swap(j, j - 1);
+            items.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+    Ok(())
+}
+
+fn heapq_nsmallest(
+    n: usize,
+    iterable: PyObjectRef,
+    key: OptionalArg<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<PyObjectRef> {
+    let mut items = vm.extract_elements(&iterable)?;
+    full_sort(vm, &mut items, key.into_option())?;
+    items.truncate(n);
+    Ok(vm.ctx.new_list(items))
+}
+
+fn heapq_nlargest(
+    n: usize,
+    iterable: PyObjectRef,
+    key: OptionalArg<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<PyObjectRef> {
+    let mut items = vm.extract_elements(&iterable)?;
+    full_sort(vm, &mut items, key.into_option())?;
+    items.reverse();
+    items.truncate(n);
+    Ok(vm.ctx.new_list(items))
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+    py_module!(ctx, "heapq", {
+        "heapify" => ctx.new_rustfunc(heapq_heapify),
+        "heappush" => ctx.new_rustfunc(heapq_heappush),
+        "heappop" => ctx.new_rustfunc(heapq_heappop),
+        "heapreplace" => ctx.new_rustfunc(heapq_heapreplace),
+        "heappushpop" => ctx.new_rustfunc(heapq_heappushpop),
+        "nlargest" => ctx.new_rustfunc(heapq_nlargest),
+        "nsmallest" => ctx.new_rustfunc(heapq_nsmallest),
+    })
+}